@@ -0,0 +1,107 @@
+use crate::worker::WorkerControl;
+use crate::{
+    draw_usage_graph_from_db, get_usage_by_date_from_db, get_usage_by_task_from_db,
+    get_usage_data_from_db, AppError, SharedConnection,
+};
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use tokio::sync::mpsc;
+use tracing::error;
+
+const USAGE_GRAPH_PATH: &str = "usage_graph.png";
+
+#[derive(Clone)]
+struct ApiState {
+    conn: SharedConnection,
+    graph_control: mpsc::Sender<WorkerControl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageQuery {
+    date: Option<String>,
+}
+
+async fn get_usage(
+    State(state): State<ApiState>,
+    Query(query): Query<UsageQuery>,
+) -> impl IntoResponse {
+    let conn = state.conn.lock().await;
+    let data = match query.date {
+        Some(date) => get_usage_by_date_from_db(&conn, &date),
+        None => get_usage_data_from_db(&conn),
+    };
+    Json(data)
+}
+
+async fn get_usage_by_task(State(state): State<ApiState>) -> impl IntoResponse {
+    let conn = state.conn.lock().await;
+    Json(get_usage_by_task_from_db(&conn))
+}
+
+async fn get_graph_png(State(state): State<ApiState>) -> impl IntoResponse {
+    {
+        let conn = state.conn.lock().await;
+        if let Err(e) = draw_usage_graph_from_db(&conn) {
+            error!("Failed to render usage graph: {:?}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "failed to render usage graph")
+                .into_response();
+        }
+    }
+
+    match tokio::fs::read(USAGE_GRAPH_PATH).await {
+        Ok(bytes) => ([(header::CONTENT_TYPE, "image/png")], bytes).into_response(),
+        Err(e) => {
+            error!("Failed to read rendered usage graph: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "failed to render usage graph").into_response()
+        },
+    }
+}
+
+async fn pause_graph(State(state): State<ApiState>) -> impl IntoResponse {
+    send_graph_control(&state, WorkerControl::Pause).await
+}
+
+async fn resume_graph(State(state): State<ApiState>) -> impl IntoResponse {
+    send_graph_control(&state, WorkerControl::Resume).await
+}
+
+async fn send_graph_control(state: &ApiState, control: WorkerControl) -> impl IntoResponse {
+    match state.graph_control.send(control).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            error!("Failed to send {:?} to graph worker: {:?}", control, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "graph worker is unreachable").into_response()
+        },
+    }
+}
+
+/// Serves usage data over a `127.0.0.1`-only HTTP API so it can be queried
+/// live, instead of only through the PNG the graph worker regenerates
+/// every 60 seconds. `graph_control` lets `/graph/pause` and `/graph/resume`
+/// reach the running graph worker without stopping the rest of the manager.
+pub async fn serve(
+    conn: SharedConnection,
+    port: u16,
+    graph_control: mpsc::Sender<WorkerControl>,
+) -> Result<(), AppError> {
+    let state = ApiState { conn, graph_control };
+
+    let app = Router::new()
+        .route("/usage", get(get_usage))
+        .route("/usage/by-task", get(get_usage_by_task))
+        .route("/graph.png", get(get_graph_png))
+        .route("/graph/pause", post(pause_graph))
+        .route("/graph/resume", post(resume_graph))
+        .with_state(state);
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
@@ -1,22 +1,57 @@
 mod active_window_tracker;
+mod api;
+mod worker;
 
 use plotters::prelude::*;
 use rusqlite::{params, Connection, Result as RusqliteResult};
 use std::collections::HashMap;
 use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::Command;
 use std::sync::Arc;
 use std::time::Duration;
+use chrono::{Local, NaiveDate};
 use thiserror::Error;
 use tokio::signal::ctrl_c;
-use tokio::time;
+use tokio::sync::{mpsc, watch, Mutex};
+use tracing::{error, info, warn};
+use windows_service::define_windows_service;
 use windows_service::service::{
-    ServiceAccess, ServiceErrorControl, ServiceStartType, ServiceState, ServiceType,
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+    ServiceStartType, ServiceState, ServiceStatus, ServiceType,
 };
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_dispatcher;
 use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use winreg::enums::{HKEY_CURRENT_USER, KEY_SET_VALUE};
+use winreg::RegKey;
 
 const SERVICE_NAME: &str = "AppUsageTracker";
 const SERVICE_TYPE: ServiceType = ServiceType::OWN_PROCESS;
 
+/// Returned by `service_dispatcher::start` when we weren't actually launched
+/// by the SCM (e.g. run from a console), so we can fall back gracefully.
+const ERROR_FAILED_SERVICE_CONTROLLER_CONNECT: i32 = 1063;
+
+const RUN_KEY_PATH: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+const PID_LOCK_FILE: &str = "app_usage.pid";
+
+/// `rusqlite::Connection` is not `Send + Sync`, so the sampler, the graph
+/// worker, and the HTTP API task all reach the database through the same
+/// mutex-guarded handle instead of sharing a raw `Connection`.
+pub type SharedConnection = Arc<Mutex<Connection>>;
+
+const DEFAULT_API_PORT: u16 = 7879;
+
+fn resolve_api_port() -> u16 {
+    std::env::var("APP_USAGE_API_PORT")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_API_PORT)
+}
+
+define_windows_service!(ffi_service_main, service_main);
+
 #[derive(Error, Debug)]
 enum AppError {
     #[error("Windows service error: {0}")]
@@ -27,6 +62,9 @@ enum AppError {
 
     #[error("Database error: {0}")]
     Database(#[from] rusqlite::Error),
+
+    #[error("Graph rendering error: {0}")]
+    Render(String),
 }
 
 #[derive(Debug)]
@@ -54,37 +92,144 @@ pub fn create_usage_table(conn: &Connection) -> RusqliteResult<()> {
 }
 
 pub fn get_usage_data_from_db(conn: &Connection) -> HashMap<String, u64> {
-    let mut stmt = conn
-        .prepare("SELECT app_name, SUM(duration) FROM app_usage GROUP BY app_name")
-        .unwrap();
-
-    let usage_iter = stmt
-        .query_map([], |row| {
-            let app_name: String = row.get(0)?;
-            let duration: u64 = row.get(1)?;
-            Ok((app_name, duration))
-        })
-        .unwrap();
+    let mut stmt = match conn.prepare("SELECT app_name, SUM(duration) FROM app_usage GROUP BY app_name") {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            error!("Failed to prepare usage data query: {:?}", e);
+            return HashMap::new();
+        },
+    };
+
+    let usage_iter = match stmt.query_map([], |row| {
+        let app_name: String = row.get(0)?;
+        let duration: u64 = row.get(1)?;
+        Ok((app_name, duration))
+    }) {
+        Ok(iter) => iter,
+        Err(e) => {
+            error!("Failed to query usage data: {:?}", e);
+            return HashMap::new();
+        },
+    };
+
+    let mut usage_data = HashMap::new();
+
+    for usage in usage_iter {
+        match usage {
+            Ok((app_name, duration)) => {
+                usage_data.insert(app_name, duration);
+            },
+            Err(e) => error!("Failed to read usage data row: {:?}", e),
+        }
+    }
+
+    usage_data
+}
+
+pub fn get_usage_by_task_from_db(conn: &Connection) -> HashMap<String, u64> {
+    let mut stmt = match conn.prepare("SELECT task, SUM(duration) FROM app_usage GROUP BY task") {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            error!("Failed to prepare usage-by-task query: {:?}", e);
+            return HashMap::new();
+        },
+    };
+
+    let usage_iter = match stmt.query_map([], |row| {
+        let task: String = row.get(0)?;
+        let duration: u64 = row.get(1)?;
+        Ok((task, duration))
+    }) {
+        Ok(iter) => iter,
+        Err(e) => {
+            error!("Failed to query usage by task: {:?}", e);
+            return HashMap::new();
+        },
+    };
 
     let mut usage_data = HashMap::new();
+    for usage in usage_iter {
+        match usage {
+            Ok((task, duration)) => {
+                usage_data.insert(task, duration);
+            },
+            Err(e) => error!("Failed to read usage-by-task row: {:?}", e),
+        }
+    }
 
+    usage_data
+}
+
+pub fn get_usage_by_date_from_db(conn: &Connection, usage_date: &str) -> HashMap<String, u64> {
+    let mut stmt = match conn.prepare(
+        "SELECT app_name, SUM(duration) FROM app_usage WHERE usage_date = ?1 GROUP BY app_name",
+    ) {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            error!("Failed to prepare usage-by-date query: {:?}", e);
+            return HashMap::new();
+        },
+    };
+
+    let usage_iter = match stmt.query_map(params![usage_date], |row| {
+        let app_name: String = row.get(0)?;
+        let duration: u64 = row.get(1)?;
+        Ok((app_name, duration))
+    }) {
+        Ok(iter) => iter,
+        Err(e) => {
+            error!("Failed to query usage for {usage_date}: {:?}", e);
+            return HashMap::new();
+        },
+    };
+
+    let mut usage_data = HashMap::new();
     for usage in usage_iter {
-        let (app_name, duration) = usage.unwrap();
-        usage_data.insert(app_name, duration);
+        match usage {
+            Ok((app_name, duration)) => {
+                usage_data.insert(app_name, duration);
+            },
+            Err(e) => error!("Failed to read usage-by-date row: {:?}", e),
+        }
     }
 
     usage_data
 }
 
-pub fn draw_usage_graph_from_db(conn: &Connection) {
+/// A palette cycled across bars so usage data with more than one app isn't
+/// rendered as a wall of identical magenta rectangles.
+const BAR_COLORS: [&RGBColor; 6] = [&MAGENTA, &RED, &BLUE, &GREEN, &CYAN, &YELLOW];
+
+/// Clamps a bar's normalized height to a finite, non-negative `i32`. A
+/// zero total or a degenerate duration can otherwise produce NaN/infinite
+/// floats that silently cast to a bogus value.
+fn finite_or_default(value: f32) -> i32 {
+    if value.is_finite() && value > 0.0 {
+        value as i32
+    } else {
+        0
+    }
+}
+
+pub fn draw_usage_graph_from_db(conn: &Connection) -> Result<(), AppError> {
     let usage_data = get_usage_data_from_db(conn);
 
     let root = BitMapBackend::new("usage_graph.png", (800, 600)).into_drawing_area();
-    root.fill(&WHITE).unwrap();
+    root.fill(&WHITE)
+        .map_err(|e| AppError::Render(e.to_string()))?;
+
+    if usage_data.is_empty() {
+        root.draw_text(
+            "No data yet",
+            &("sans-serif", 30).into_font().color(&BLACK),
+            (300, 280),
+        )
+        .map_err(|e| AppError::Render(e.to_string()))?;
+        return Ok(());
+    }
 
-    let max_duration = usage_data.values().max().unwrap_or(&0);
-    let y_max = if *max_duration == 0 { 1 } else { *max_duration };
-    let y_axis_max = 100;
+    let y_max = usage_data.values().copied().max().unwrap_or(0).max(1);
+    let y_axis_max = y_max as i32;
 
     let mut chart = ChartBuilder::on(&root)
         .caption("Application Usage Over Time", ("sans-serif", 50).into_font())
@@ -92,24 +237,27 @@ pub fn draw_usage_graph_from_db(conn: &Connection) {
         .x_label_area_size(30)
         .y_label_area_size(40)
         .build_cartesian_2d(0..usage_data.len() as i32, 0..y_axis_max)
-        .unwrap();
+        .map_err(|e| AppError::Render(e.to_string()))?;
 
-    chart.configure_mesh().draw().unwrap();
+    chart
+        .configure_mesh()
+        .draw()
+        .map_err(|e| AppError::Render(e.to_string()))?;
 
-    let colors = vec![&MAGENTA];
     let bar_width = 1;
     let default_font_size = 12;
 
     for (i, (app_name, duration)) in usage_data.iter().enumerate() {
-        let color = colors[i % colors.len()];
-        let normalized_duration = (*duration as f32 / y_max as f32 * y_axis_max as f32) as i32;
+        let color = BAR_COLORS[i % BAR_COLORS.len()];
+        let normalized_duration =
+            finite_or_default(*duration as f32 / y_max as f32 * y_axis_max as f32);
 
         chart
             .draw_series(std::iter::once(Rectangle::new(
                 [(i as i32, 0), (i as i32 + bar_width, normalized_duration)],
                 color.filled(),
             )))
-            .unwrap();
+            .map_err(|e| AppError::Render(e.to_string()))?;
 
         let text_color = &BLACK;
         let text_position = (i as i32 + bar_width / 2, normalized_duration / 2);
@@ -124,52 +272,27 @@ pub fn draw_usage_graph_from_db(conn: &Connection) {
                     .color(text_color)
                     // .transform(FontTransform::Rotate90),
             )))
-            .unwrap();
+            .map_err(|e| AppError::Render(e.to_string()))?;
     }
 
     chart
         .configure_series_labels()
         .border_style(&BLACK)
         .draw()
-        .unwrap();
-}
-
-pub async fn track_processes(conn: Arc<Connection>) {
-    let mut interval = time::interval(Duration::from_secs(1));
-    let mut graph_interval = time::interval(Duration::from_secs(60));
-    let mut i = 0;
-    let mut idle = false;
-
-    loop {
-        tokio::select! {
-            _ = interval.tick() => {
-                i += 1;
+        .map_err(|e| AppError::Render(e.to_string()))?;
 
-                if i == IDLE_CHECK_SECS {
-                    let duration = active_window_tracker::get_last_input().as_secs();
-                    idle = duration > IDLE_PERIOD;
-                    i = 0;
-                }
-
-                if !idle {
-                    let (window_pid, window_title) = active_window_tracker::get_active_window();
+    Ok(())
+}
 
-                    if window_pid != 0 {
-                        get_process(&conn, &window_title);
-                    }
-                }
-            },
-            _ = graph_interval.tick() => {
-                println!("Generating usage graph...");
-                draw_usage_graph_from_db(&conn);
-            },
-            _ = ctrl_c() => {
-                println!("Received shutdown signal, generating final usage graph...");
-                draw_usage_graph_from_db(&conn);
-                break;
-            },
-        }
-    }
+/// Resolves "today" in the user's local timezone rather than UTC, so usage
+/// recorded just after local midnight lands in the right day's bucket.
+/// `Local::now()` is already a resolved wall-clock instant rather than a
+/// naive local timestamp being re-interpreted, so unlike the latter it can
+/// never land in a DST spring-forward gap or a fall-back overlap -- there is
+/// no ambiguity left to resolve, and no UTC fallback that would reintroduce
+/// the skew this function exists to avoid.
+fn current_usage_date() -> NaiveDate {
+    Local::now().date_naive()
 }
 
 pub fn get_process(conn: &Connection, window_title: &str) {
@@ -185,17 +308,20 @@ pub fn get_process(conn: &Connection, window_title: &str) {
     let task = parts[..parts.len() - 1].join(" - ").trim().to_string();
 
     let duration = 1;
-    let usage_date = chrono::Utc::now().date_naive();
+    let usage_date = current_usage_date();
 
     let usage_date_str = usage_date.format("%Y-%m-%d").to_string();
 
-    conn.execute(
+    let result = conn.execute(
         "INSERT INTO app_usage (task, app_name, duration, usage_date)
          VALUES (?1, ?2, ?3, ?4)
          ON CONFLICT(task, app_name, usage_date) DO UPDATE SET duration = duration + ?3",
         params![task, app_name, duration, usage_date_str],
-    )
-    .unwrap();
+    );
+
+    if let Err(e) = result {
+        error!("Failed to record usage for {app_name}: {:?}", e);
+    }
 }
 
 fn install_service() -> Result<(), AppError> {
@@ -265,6 +391,113 @@ fn delete_service() -> Result<(), AppError> {
     Ok(())
 }
 
+/// Registers the executable under the HKCU Run key so it starts at logon
+/// without requiring administrator rights, and launches it immediately
+/// since a Run-key entry only runs at the next logon otherwise.
+fn install_user() -> Result<(), AppError> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let run_key = hkcu.open_subkey_with_flags(RUN_KEY_PATH, KEY_SET_VALUE)?;
+
+    let exe_path = std::env::current_exe()?;
+    run_key.set_value(SERVICE_NAME, &exe_path.display().to_string())?;
+
+    Command::new(&exe_path).spawn()?;
+
+    Ok(())
+}
+
+/// Removes the HKCU Run key entry and terminates the currently running
+/// unmanaged instance, located via the PID it wrote to `PID_LOCK_FILE`
+/// on startup. The PID is checked against the running process's image name
+/// before being killed, since the lock file is advisory: if the tracked
+/// process already exited, the OS is free to recycle its PID for something
+/// unrelated.
+fn uninstall_user() -> Result<(), AppError> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let run_key = hkcu.open_subkey_with_flags(RUN_KEY_PATH, KEY_SET_VALUE)?;
+    let _ = run_key.delete_value(SERVICE_NAME);
+
+    if let Some(pid) = read_pid_lock_file() {
+        if is_our_process(pid) {
+            let _ = Command::new("taskkill")
+                .args(["/PID", &pid.to_string(), "/F"])
+                .status();
+        } else {
+            warn!("PID {pid} from the lock file no longer matches this executable, leaving it alone");
+        }
+    }
+    remove_pid_lock_file();
+
+    Ok(())
+}
+
+/// Checks that `pid` is still running this same executable, via `tasklist`,
+/// so a stale lock file left behind by a process that already exited can't
+/// cause us to force-kill whatever unrelated process the OS recycled the
+/// PID to.
+fn is_our_process(pid: u32) -> bool {
+    let Ok(exe_name) = std::env::current_exe().map(|path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }) else {
+        return false;
+    };
+
+    let Ok(output) = Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .output()
+    else {
+        return false;
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .and_then(|line| line.split(',').next())
+        .map(|image_name| image_name.trim_matches('"'))
+        .is_some_and(|image_name| image_name.eq_ignore_ascii_case(&exe_name))
+}
+
+/// Directory the PID lock file and SQLite database live in, anchored to the
+/// executable's own directory rather than the process's current directory:
+/// the per-user flow's different invocations run with different working
+/// directories (a Run-key logon launch starts with cwd `%windir%\system32`,
+/// often unwritable for a non-admin, while `--uninstall-user` runs from the
+/// user's own shell), so install/run/uninstall must agree on a fixed
+/// location instead of a bare relative path.
+fn app_data_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(|dir| dir.to_path_buf()))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn pid_lock_file_path() -> PathBuf {
+    app_data_dir().join(PID_LOCK_FILE)
+}
+
+fn db_path() -> PathBuf {
+    app_data_dir().join("app_usage.db")
+}
+
+/// Records this process's PID so a later `--uninstall-user` can find and
+/// terminate it; only meaningful for the unmanaged Run-key deployment.
+fn write_pid_lock_file() -> Result<(), AppError> {
+    std::fs::write(pid_lock_file_path(), std::process::id().to_string())?;
+    Ok(())
+}
+
+fn read_pid_lock_file() -> Option<u32> {
+    std::fs::read_to_string(pid_lock_file_path())
+        .ok()
+        .and_then(|contents| contents.trim().parse().ok())
+}
+
+fn remove_pid_lock_file() {
+    let _ = std::fs::remove_file(pid_lock_file_path());
+}
+
 fn get_service_status() -> Result<ServiceState, AppError> {
     let manager_access = ServiceManagerAccess::CONNECT;
     let service_manager = ServiceManager::local_computer(None::<&str>, manager_access)?;
@@ -275,15 +508,234 @@ fn get_service_status() -> Result<ServiceState, AppError> {
     Ok(status.current_state)
 }
 
-async fn service_main() {
-    let conn = Arc::new(Connection::open("app_usage.db").expect("Could not open database"));
-    create_usage_table(&conn).expect("Could not create usage table");
+/// Entry point invoked by the SCM (via `ffi_service_main`) on its own thread.
+/// This is intentionally synchronous: the macro-generated FFI shim cannot
+/// call into an `async fn`, so we own a dedicated `tokio` runtime here,
+/// separate from the one used in console mode.
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(e) = run_service() {
+        error!("Service failed: {:?}", e);
+    }
+}
+
+fn run_service() -> Result<(), AppError> {
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    let event_handler = move |control_event| -> ServiceControlHandlerResult {
+        match control_event {
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                let _ = shutdown_tx.send(true);
+                ServiceControlHandlerResult::NoError
+            },
+            _ => ServiceControlHandlerResult::NotImplemented,
+        }
+    };
+
+    let status_handle = service_control_handler::register(SERVICE_NAME, event_handler)?;
+
+    report_status(
+        &status_handle,
+        ServiceState::StartPending,
+        ServiceControlAccept::empty(),
+        1,
+        SERVICE_PENDING_WAIT_HINT,
+    )?;
+
+    report_status(
+        &status_handle,
+        ServiceState::Running,
+        ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+        0,
+        Duration::default(),
+    )?;
 
-    track_processes(conn.clone()).await;
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let conn: SharedConnection = Arc::new(Mutex::new(Connection::open(db_path())?));
+        {
+            let guard = conn.lock().await;
+            create_usage_table(&guard)?;
+            worker::create_worker_config_table(&guard)?;
+        }
+
+        // The sender lets the HTTP API pause/resume the graph worker
+        // without stopping the window sampler.
+        let (graph_control_tx, graph_control_rx) = mpsc::channel(4);
+
+        let api_conn = conn.clone();
+        let api_port = resolve_api_port();
+        tokio::spawn(async move {
+            if let Err(e) = api::serve(api_conn, api_port, graph_control_tx).await {
+                error!("HTTP API server failed: {:?}", e);
+            }
+        });
+
+        let mut workers = worker::WorkerManager::new(conn.clone());
+        workers.register(Box::new(worker::WindowSamplerWorker::new(conn.clone())));
+        workers.register(Box::new(worker::GraphRendererWorker::new(
+            conn,
+            graph_control_rx,
+        )));
+        workers.run(shutdown_rx).await;
+        Ok::<(), AppError>(())
+    })?;
+
+    report_status(
+        &status_handle,
+        ServiceState::StopPending,
+        ServiceControlAccept::empty(),
+        2,
+        SERVICE_PENDING_WAIT_HINT,
+    )?;
+
+    report_status(
+        &status_handle,
+        ServiceState::Stopped,
+        ServiceControlAccept::empty(),
+        0,
+        Duration::default(),
+    )?;
+
+    Ok(())
 }
 
-#[tokio::main]
-async fn main() -> Result<(), AppError> {
+/// How long the SCM should wait before expecting another checkpoint while
+/// we're in a `*Pending` state, so a slow (but not hung) start/stop doesn't
+/// get the service killed for looking unresponsive.
+const SERVICE_PENDING_WAIT_HINT: Duration = Duration::from_secs(3);
+
+/// `checkpoint` must increase on each report during a `*Pending` state (the
+/// SCM uses a stalled checkpoint, not just elapsed time, to decide the
+/// service has hung) and `wait_hint` is the caller's estimate of how long
+/// until the next one; steady states (`Running`/`Stopped`) report both as
+/// zero, per the Windows service API's own convention.
+fn report_status(
+    status_handle: &windows_service::service_control_handler::ServiceStatusHandle,
+    current_state: ServiceState,
+    controls_accepted: ServiceControlAccept,
+    checkpoint: u32,
+    wait_hint: Duration,
+) -> Result<(), AppError> {
+    status_handle.set_service_status(ServiceStatus {
+        service_type: SERVICE_TYPE,
+        current_state,
+        controls_accepted,
+        exit_code: ServiceExitCode::Win32(0),
+        checkpoint,
+        wait_hint,
+        process_id: None,
+    })?;
+    Ok(())
+}
+
+/// Runs the tracker on a plain console/terminal, outside of SCM control,
+/// using Ctrl+C as the shutdown signal instead of a service control event.
+fn run_console_mode() -> Result<(), AppError> {
+    write_pid_lock_file()?;
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let result = runtime.block_on(async {
+        let conn: SharedConnection = Arc::new(Mutex::new(Connection::open(db_path())?));
+        {
+            let guard = conn.lock().await;
+            create_usage_table(&guard)?;
+            worker::create_worker_config_table(&guard)?;
+        }
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        tokio::spawn(async move {
+            let _ = ctrl_c().await;
+            let _ = shutdown_tx.send(true);
+        });
+
+        let (graph_control_tx, graph_control_rx) = mpsc::channel(4);
+
+        let api_conn = conn.clone();
+        let api_port = resolve_api_port();
+        tokio::spawn(async move {
+            if let Err(e) = api::serve(api_conn, api_port, graph_control_tx).await {
+                error!("HTTP API server failed: {:?}", e);
+            }
+        });
+
+        let mut workers = worker::WorkerManager::new(conn.clone());
+        workers.register(Box::new(worker::WindowSamplerWorker::new(conn.clone())));
+        workers.register(Box::new(worker::GraphRendererWorker::new(
+            conn,
+            graph_control_rx,
+        )));
+        workers.run(shutdown_rx).await;
+        Ok::<(), AppError>(())
+    });
+
+    remove_pid_lock_file();
+    result
+}
+
+/// Prints each known worker's name and last known state, read from the
+/// `worker_config` table so it works without a running service instance.
+fn print_workers() -> Result<(), AppError> {
+    let conn = Connection::open(db_path())?;
+    worker::create_worker_config_table(&conn)?;
+    worker::print_worker_statuses(&conn);
+    Ok(())
+}
+
+/// Persists a worker's tranquility knob so a running service picks it up on
+/// its next read, without needing a control channel into the service itself.
+fn set_worker_tranquility(worker_name: &str, tranquility: &str) -> Result<(), AppError> {
+    let tranquility: u8 = tranquility.parse().map_err(|_| {
+        AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("tranquility must be an integer between 0 and {}", worker::MAX_TRANQUILITY),
+        ))
+    })?;
+    if tranquility > worker::MAX_TRANQUILITY {
+        return Err(AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("tranquility must be between 0 and {}", worker::MAX_TRANQUILITY),
+        )));
+    }
+
+    let conn = Connection::open(db_path())?;
+    worker::create_worker_config_table(&conn)?;
+    worker::set_tranquility(&conn, worker_name, tranquility)?;
+    println!("Set tranquility for {worker_name} to {tranquility}.");
+    Ok(())
+}
+
+/// Initializes hourly-rolling-file logging next to the executable, since a
+/// headless service has no console to write to. The returned guard must be
+/// kept alive for the process lifetime or buffered log lines are dropped.
+fn init_logging() -> Result<tracing_appender::non_blocking::WorkerGuard, AppError> {
+    let file_appender = tracing_appender::rolling::hourly(app_data_dir(), "app_usage.log");
+    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+
+    tracing_subscriber::fmt()
+        .with_writer(non_blocking)
+        .with_ansi(false)
+        .init();
+
+    Ok(guard)
+}
+
+fn main() {
+    let _log_guard = match init_logging() {
+        Ok(guard) => Some(guard),
+        Err(e) => {
+            eprintln!("Failed to initialize logging, continuing without it: {:?}", e);
+            None
+        },
+    };
+
+    if let Err(e) = run() {
+        error!("Fatal error: {:?}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), AppError> {
     let args: Vec<String> = std::env::args().collect();
 
     if args.len() > 1 {
@@ -324,14 +776,41 @@ async fn main() -> Result<(), AppError> {
                 }
                 return Ok(());
             },
-            _ => eprintln!("Unknown command. Use --install, --uninstall, --start, --stop, --delete, or --status."),
+            "--workers" => {
+                print_workers()?;
+                return Ok(());
+            },
+            "--install-user" => {
+                install_user()?;
+                println!("Installed for the current user and started successfully.");
+                return Ok(());
+            },
+            "--uninstall-user" => {
+                uninstall_user()?;
+                println!("Uninstalled for the current user successfully.");
+                return Ok(());
+            },
+            "--set-tranquility" => {
+                match (args.get(2), args.get(3)) {
+                    (Some(worker_name), Some(tranquility)) => {
+                        set_worker_tranquility(worker_name, tranquility)?;
+                    },
+                    _ => eprintln!("Usage: --set-tranquility <worker-name> <0..={}>", worker::MAX_TRANQUILITY),
+                }
+                return Ok(());
+            },
+            _ => eprintln!("Unknown command. Use --install, --uninstall, --start, --stop, --delete, --status, --workers, --install-user, --uninstall-user, or --set-tranquility."),
         }
+        return Ok(());
     }
 
-    let conn = Arc::new(Connection::open("app_usage.db")?);
-    create_usage_table(&conn)?;
-
-    service_main().await;
-
-    Ok(())
+    match service_dispatcher::start(SERVICE_NAME, ffi_service_main) {
+        Ok(()) => Ok(()),
+        Err(windows_service::Error::Winapi(ref e))
+            if e.raw_os_error() == Some(ERROR_FAILED_SERVICE_CONTROLLER_CONNECT) =>
+        {
+            run_console_mode()
+        },
+        Err(e) => Err(AppError::from(e)),
+    }
 }
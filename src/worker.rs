@@ -0,0 +1,302 @@
+use crate::active_window_tracker;
+use crate::{draw_usage_graph_from_db, get_process, SharedConnection, IDLE_CHECK_SECS, IDLE_PERIOD};
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::time;
+use tracing::{error, info};
+
+/// Upper bound of the tranquility knob: 0 means "no extra throttling",
+/// 10 means "sleep as much as we reasonably can between samples".
+pub const MAX_TRANQUILITY: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerState {
+    Active,
+    Idle,
+    Dead,
+}
+
+impl WorkerState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            WorkerState::Active => "Active",
+            WorkerState::Idle => "Idle",
+            WorkerState::Dead => "Dead",
+        }
+    }
+}
+
+/// Control messages a worker's owner can send it without stopping the
+/// whole manager, e.g. so the graph worker can be paused while the window
+/// sampler keeps running.
+#[derive(Debug, Clone, Copy)]
+pub enum WorkerControl {
+    Pause,
+    Resume,
+}
+
+#[async_trait]
+pub trait Worker: Send {
+    fn name(&self) -> &str;
+    async fn step(&mut self) -> WorkerState;
+}
+
+pub fn create_worker_config_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS worker_config (
+            worker_name TEXT PRIMARY KEY,
+            tranquility INTEGER NOT NULL DEFAULT 0,
+            last_state TEXT NOT NULL DEFAULT 'Idle'
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Reads a worker's persisted tranquility, defaulting to 0 (untouched) the
+/// first time a worker runs or if the row is missing entirely.
+pub fn get_tranquility(conn: &Connection, worker_name: &str) -> u8 {
+    conn.query_row(
+        "SELECT tranquility FROM worker_config WHERE worker_name = ?1",
+        params![worker_name],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|v| v.clamp(0, MAX_TRANQUILITY as i64) as u8)
+    .unwrap_or(0)
+}
+
+pub fn set_tranquility(conn: &Connection, worker_name: &str, tranquility: u8) -> rusqlite::Result<()> {
+    let tranquility = tranquility.min(MAX_TRANQUILITY);
+    conn.execute(
+        "INSERT INTO worker_config (worker_name, tranquility, last_state)
+         VALUES (?1, ?2, 'Idle')
+         ON CONFLICT(worker_name) DO UPDATE SET tranquility = ?2",
+        params![worker_name, tranquility as i64],
+    )?;
+    Ok(())
+}
+
+fn record_state(conn: &Connection, worker_name: &str, state: WorkerState) {
+    let result = conn.execute(
+        "INSERT INTO worker_config (worker_name, tranquility, last_state)
+         VALUES (?1, 0, ?2)
+         ON CONFLICT(worker_name) DO UPDATE SET last_state = ?2",
+        params![worker_name, state.as_str()],
+    );
+    if let Err(e) = result {
+        error!("Failed to persist worker state for {}: {:?}", worker_name, e);
+    }
+}
+
+/// Prints the name and last known state of every worker with a row in
+/// `worker_config` (one that has reported in at least once, or whose
+/// tranquility was set, which records it as `Idle`), read from the table so
+/// this works from a plain CLI invocation without talking to a running service.
+pub fn print_worker_statuses(conn: &Connection) {
+    let mut stmt = match conn.prepare(
+        "SELECT worker_name, tranquility, last_state FROM worker_config ORDER BY worker_name",
+    ) {
+        Ok(stmt) => stmt,
+        Err(e) => {
+            eprintln!("Failed to query worker status: {:?}", e);
+            return;
+        },
+    };
+
+    let rows = stmt.query_map([], |row| {
+        let name: String = row.get(0)?;
+        let tranquility: i64 = row.get(1)?;
+        let state: String = row.get(2)?;
+        Ok((name, tranquility, state))
+    });
+
+    match rows {
+        Ok(rows) => {
+            for (name, tranquility, state) in rows.flatten() {
+                println!("{name}: {state} (tranquility={tranquility})");
+            }
+        },
+        Err(e) => eprintln!("Failed to read worker status rows: {:?}", e),
+    }
+}
+
+/// Turns the 0..=10 tranquility knob into the number of manager ticks the
+/// sampler waits between samples (1 tick at 0, up to 11 at full
+/// tranquility). Skipping ticks rather than `sleep`ing inside `step` keeps
+/// the knob from blocking the manager's shared tick loop -- and, with it,
+/// every other worker's progress and the shutdown signal.
+fn tranquility_tick_interval(tranquility: u8) -> u32 {
+    u32::from(tranquility.min(MAX_TRANQUILITY)) + 1
+}
+
+pub struct WindowSamplerWorker {
+    conn: SharedConnection,
+    tick: i32,
+    idle: bool,
+    ticks_since_sample: u32,
+}
+
+impl WindowSamplerWorker {
+    pub fn new(conn: SharedConnection) -> Self {
+        Self {
+            conn,
+            tick: 0,
+            idle: false,
+            ticks_since_sample: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl Worker for WindowSamplerWorker {
+    fn name(&self) -> &str {
+        "window-sampler"
+    }
+
+    async fn step(&mut self) -> WorkerState {
+        let tranquility = {
+            let conn = self.conn.lock().await;
+            get_tranquility(&conn, self.name())
+        };
+
+        self.ticks_since_sample += 1;
+        if self.ticks_since_sample < tranquility_tick_interval(tranquility) {
+            return WorkerState::Idle;
+        }
+        self.ticks_since_sample = 0;
+
+        self.tick += 1;
+
+        if self.tick == IDLE_CHECK_SECS {
+            let duration = active_window_tracker::get_last_input().as_secs();
+            self.idle = duration > IDLE_PERIOD;
+            self.tick = 0;
+        }
+
+        if self.idle {
+            WorkerState::Idle
+        } else {
+            let (window_pid, window_title) = active_window_tracker::get_active_window();
+            if window_pid != 0 {
+                let conn = self.conn.lock().await;
+                get_process(&conn, &window_title);
+                WorkerState::Active
+            } else {
+                WorkerState::Idle
+            }
+        }
+    }
+}
+
+/// Ticks of the 1-second manager loop between graph renders. Kept as a
+/// self-managed cadence (rather than its own `time::interval`) so the
+/// manager can drive every worker through one uniform tick.
+const GRAPH_RENDER_EVERY_TICKS: u32 = 60;
+
+pub struct GraphRendererWorker {
+    conn: SharedConnection,
+    control: mpsc::Receiver<WorkerControl>,
+    paused: bool,
+    ticks_since_render: u32,
+}
+
+impl GraphRendererWorker {
+    pub fn new(conn: SharedConnection, control: mpsc::Receiver<WorkerControl>) -> Self {
+        Self {
+            conn,
+            control,
+            paused: false,
+            ticks_since_render: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl Worker for GraphRendererWorker {
+    fn name(&self) -> &str {
+        "graph-renderer"
+    }
+
+    async fn step(&mut self) -> WorkerState {
+        while let Ok(control) = self.control.try_recv() {
+            match control {
+                WorkerControl::Pause => self.paused = true,
+                WorkerControl::Resume => self.paused = false,
+            }
+        }
+
+        if self.paused {
+            return WorkerState::Idle;
+        }
+
+        self.ticks_since_render += 1;
+        if self.ticks_since_render < GRAPH_RENDER_EVERY_TICKS {
+            return WorkerState::Idle;
+        }
+        self.ticks_since_render = 0;
+
+        info!("Generating usage graph...");
+        let conn = self.conn.lock().await;
+        match draw_usage_graph_from_db(&conn) {
+            Ok(()) => WorkerState::Active,
+            Err(e) => {
+                error!("Failed to render usage graph: {:?}", e);
+                WorkerState::Idle
+            },
+        }
+    }
+}
+
+/// Drives an arbitrary set of registered workers through `Worker::step` on
+/// a uniform 1-second tick. Each worker owns its own cadence internally
+/// (e.g. the graph renderer only actually renders on every 60th tick), so
+/// new workers — including future exporters — can be registered without
+/// touching this loop.
+pub struct WorkerManager {
+    conn: SharedConnection,
+    workers: Vec<Box<dyn Worker>>,
+}
+
+impl WorkerManager {
+    pub fn new(conn: SharedConnection) -> Self {
+        Self {
+            conn,
+            workers: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, worker: Box<dyn Worker>) {
+        self.workers.push(worker);
+    }
+
+    pub async fn run(&mut self, mut shutdown: watch::Receiver<bool>) {
+        let mut tick = time::interval(Duration::from_secs(1));
+
+        loop {
+            tokio::select! {
+                _ = tick.tick() => {
+                    for worker in self.workers.iter_mut() {
+                        let state = worker.step().await;
+                        let conn = self.conn.lock().await;
+                        record_state(&conn, worker.name(), state);
+                    }
+                },
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Received shutdown signal, generating final usage graph...");
+                        let conn = self.conn.lock().await;
+                        if let Err(e) = draw_usage_graph_from_db(&conn) {
+                            error!("Failed to render final usage graph: {:?}", e);
+                        }
+                        for worker in self.workers.iter() {
+                            record_state(&conn, worker.name(), WorkerState::Dead);
+                        }
+                        break;
+                    }
+                },
+            }
+        }
+    }
+}